@@ -2,10 +2,12 @@ use std::sync::Arc;
 
 use thiserror::Error;
 
-use crate::time::{Time, TimeCompatible, TimeFn};
+use crate::time::{FemtoInt, FemtoTime, Time, TimeFn, FEMTOS_PER_SEC};
 
+pub use file::{FileBackend, PcmFormat};
 pub use rodio::RodioBackend;
 
+mod file;
 mod rodio;
 
 pub type Sample = f32;
@@ -19,9 +21,9 @@ pub struct SampleSource<T> {
     channels: Arc<[T]>,
     curr_channel: usize,
     sample_rate: u32,
-    second_sample: u32,
-    start: Time,
-    end: Time,
+    sample_index: u64,
+    start: FemtoTime,
+    end: FemtoTime,
 }
 
 impl<T> Clone for SampleSource<T> {
@@ -30,13 +32,46 @@ impl<T> Clone for SampleSource<T> {
             channels: self.channels.clone(),
             curr_channel: self.curr_channel,
             sample_rate: self.sample_rate,
-            second_sample: 0,
+            sample_index: 0,
             start: self.start,
             end: self.end,
         }
     }
 }
 
+impl<T> SampleSource<T> {
+    pub(crate) fn new(
+        channels: Arc<[T]>,
+        sample_rate: u32,
+        start: Time,
+        end: Time,
+    ) -> Self {
+        Self {
+            channels,
+            curr_channel: 0,
+            sample_rate,
+            sample_index: 0,
+            start: FemtoTime::from_secs_f32(start),
+            end: FemtoTime::from_secs_f32(end),
+        }
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+            .len()
+            .try_into()
+            .expect("non-supported number of channels")
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.end.femtos() < FemtoInt::MAX
+    }
+}
+
 impl<T> Iterator for SampleSource<T>
 where
     T: TimeFn<Output = Sample>,
@@ -44,19 +79,35 @@ where
     type Item = T::Output;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let offset = (self.second_sample as TimeCompatible)
-            / (self.sample_rate as TimeCompatible);
+        if self.sample_rate == 0 {
+            // A zero sample rate can't be divided into femtoseconds; mirror
+            // the old f32 behavior (NaN/Infinity offsets) by emitting
+            // nothing instead of panicking on the division below.
+            return None;
+        }
+
+        let sample_rate = self.sample_rate as FemtoInt;
+        let sample_index = self.sample_index as FemtoInt;
+
+        // Split femtos-per-sample into a whole part and a remainder so that
+        // multiplying by `sample_index` yields no product larger than the
+        // offset itself: a naive `sample_index * FEMTOS_PER_SEC / sample_rate`
+        // overflows the intermediate product almost immediately on the
+        // narrower wasm32 fallback. The offset itself still overflows there
+        // once it passes `i64::MAX` femtoseconds (about 2.5 hours).
+        let per_sample = FemtoTime::from_femtos(FEMTOS_PER_SEC) / sample_rate;
+        let per_sample_remainder =
+            FemtoTime::from_femtos(FEMTOS_PER_SEC) - per_sample * sample_rate;
+        let offset = per_sample * sample_index
+            + (per_sample_remainder * sample_index) / sample_rate;
         let curr_time = self.start + offset;
 
         if curr_time <= self.end {
-            let data = self.channels[self.curr_channel].at(curr_time);
+            let data =
+                self.channels[self.curr_channel].at(curr_time.as_secs_f32());
             self.curr_channel += 1;
             if self.curr_channel >= self.channels.len() {
-                self.second_sample += 1;
-                if self.second_sample >= self.sample_rate {
-                    self.sample_rate = 0;
-                    self.start += 1.0;
-                }
+                self.sample_index += 1;
                 self.curr_channel = 0;
             }
             Some(data)
@@ -115,14 +166,8 @@ where
     where
         T: Send + Sync + 'static,
     {
-        let source = SampleSource {
-            channels: self.channels.clone(),
-            curr_channel: 0,
-            sample_rate: self.sample_rate,
-            second_sample: 0,
-            start,
-            end,
-        };
+        let source =
+            SampleSource::new(self.channels.clone(), self.sample_rate, start, end);
 
         self.backend.stop();
         self.backend.play(source);
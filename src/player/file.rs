@@ -0,0 +1,111 @@
+use core::fmt;
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use hound::{SampleFormat, WavSpec, WavWriter};
+
+use crate::time::TimeFn;
+
+use super::{Backend, Sample, SampleSource};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PcmFormat {
+    F32,
+    I16,
+}
+
+pub struct FileBackend {
+    path: PathBuf,
+    format: PcmFormat,
+    stopped: Arc<AtomicBool>,
+    render: Option<JoinHandle<Result<(), hound::Error>>>,
+}
+
+impl fmt::Debug for FileBackend {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_struct("FileBackend")
+            .field("path", &self.path)
+            .field("format", &self.format)
+            .finish()
+    }
+}
+
+impl FileBackend {
+    pub fn new(path: impl Into<PathBuf>, format: PcmFormat) -> Self {
+        Self {
+            path: path.into(),
+            format,
+            stopped: Arc::new(AtomicBool::new(false)),
+            render: None,
+        }
+    }
+}
+
+impl Backend for FileBackend {
+    fn play<T>(&mut self, source: SampleSource<T>)
+    where
+        T: TimeFn<Output = Sample> + Send + Sync + 'static,
+    {
+        assert!(
+            source.is_finite(),
+            "FileBackend can only render a source with a finite [start, end] \
+             window, not an unbounded/looping one",
+        );
+
+        let path = self.path.clone();
+        let format = self.format;
+        let stopped = Arc::clone(&self.stopped);
+        stopped.store(false, Ordering::SeqCst);
+
+        self.render = Some(thread::spawn(move || {
+            let spec = WavSpec {
+                channels: source.channels(),
+                sample_rate: source.sample_rate(),
+                bits_per_sample: match format {
+                    PcmFormat::F32 => 32,
+                    PcmFormat::I16 => 16,
+                },
+                sample_format: match format {
+                    PcmFormat::F32 => SampleFormat::Float,
+                    PcmFormat::I16 => SampleFormat::Int,
+                },
+            };
+
+            let mut writer = WavWriter::create(&path, spec)?;
+
+            for sample in source {
+                if stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                match format {
+                    PcmFormat::F32 => writer.write_sample(sample)?,
+                    PcmFormat::I16 => writer.write_sample(
+                        (sample.clamp(-1.0, 1.0) * i16::MAX as Sample) as i16,
+                    )?,
+                }
+            }
+
+            writer.finalize()
+        }));
+    }
+
+    fn stop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        self.wait();
+    }
+
+    fn wait(&mut self) {
+        if let Some(render) = self.render.take() {
+            render
+                .join()
+                .expect("render thread panicked")
+                .expect("failed to render audio to file");
+        }
+    }
+}
@@ -16,14 +16,11 @@ where
     }
 
     fn channels(&self) -> u16 {
-        self.channels
-            .len()
-            .try_into()
-            .expect("non-supported number of channels for rodio")
+        SampleSource::channels(self)
     }
 
     fn sample_rate(&self) -> u32 {
-        self.sample_rate
+        SampleSource::sample_rate(self)
     }
 
     fn total_duration(&self) -> Option<Duration> {
@@ -1,4 +1,9 @@
-use std::{cmp::Ordering, rc::Rc, sync::Arc};
+use std::{
+    cmp::Ordering,
+    ops::{Add, Div, Mul, Sub},
+    rc::Rc,
+    sync::Arc,
+};
 
 use thiserror::Error;
 
@@ -6,6 +11,69 @@ pub type TimeCompatible = f32;
 
 pub type Time = TimeCompatible;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub type FemtoInt = i128;
+
+#[cfg(target_arch = "wasm32")]
+pub type FemtoInt = i64;
+
+pub const FEMTOS_PER_SEC: FemtoInt = 1_000_000_000_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct FemtoTime(FemtoInt);
+
+impl FemtoTime {
+    pub const ZERO: Self = Self(0);
+
+    pub fn from_femtos(femtos: FemtoInt) -> Self {
+        Self(femtos)
+    }
+
+    pub fn femtos(self) -> FemtoInt {
+        self.0
+    }
+
+    pub fn from_secs_f32(secs: TimeCompatible) -> Self {
+        Self((secs as f64 * FEMTOS_PER_SEC as f64) as FemtoInt)
+    }
+
+    pub fn as_secs_f32(self) -> TimeCompatible {
+        (self.0 as f64 / FEMTOS_PER_SEC as f64) as TimeCompatible
+    }
+}
+
+impl Add for FemtoTime {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for FemtoTime {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl Mul<FemtoInt> for FemtoTime {
+    type Output = Self;
+
+    fn mul(self, scalar: FemtoInt) -> Self {
+        Self(self.0 * scalar)
+    }
+}
+
+impl Div<FemtoInt> for FemtoTime {
+    type Output = Self;
+
+    fn div(self, scalar: FemtoInt) -> Self {
+        Self(self.0 / scalar)
+    }
+}
+
 pub type DynTimeFn<'t, A> = dyn TimeFn<Output = A> + 't + Send + Sync;
 
 pub type UnsyncDynTimeFn<'t, A> = dyn TimeFn<Output = A> + 't;
@@ -430,6 +498,191 @@ where
     }
 }
 
+#[derive(Debug, Clone, Error)]
+pub enum BadAutomationPoint {
+    #[error("breakpoint seconds cannot be NaN")]
+    Nan,
+    #[error("duplicated breakpoint: {0} seconds")]
+    Duplicated(Time),
+    #[error("automation requires at least one breakpoint")]
+    Empty,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct AffineTransform {
+    scale: TimeCompatible,
+    offset: TimeCompatible,
+}
+
+impl AffineTransform {
+    const IDENTITY: Self = Self { scale: 1.0, offset: 0.0 };
+
+    fn apply(self, value: TimeCompatible) -> TimeCompatible {
+        self.scale * value + self.offset
+    }
+
+    fn then(self, next: Self) -> Self {
+        Self {
+            scale: next.scale * self.scale,
+            offset: next.scale * self.offset + next.offset,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SegmentTree {
+    len: usize,
+    values: Vec<TimeCompatible>,
+    pending: Vec<AffineTransform>,
+}
+
+impl SegmentTree {
+    fn new(values: Vec<TimeCompatible>) -> Self {
+        let len = values.len();
+        let pending = vec![AffineTransform::IDENTITY; 4 * len.max(1)];
+        Self { len, values, pending }
+    }
+
+    fn push_down(&mut self, node: usize) {
+        let transform = self.pending[node];
+        self.pending[node * 2] = self.pending[node * 2].then(transform);
+        self.pending[node * 2 + 1] = self.pending[node * 2 + 1].then(transform);
+        self.pending[node] = AffineTransform::IDENTITY;
+    }
+
+    fn range_apply(
+        &mut self,
+        node: usize,
+        node_lo: usize,
+        node_hi: usize,
+        lo: usize,
+        hi: usize,
+        transform: AffineTransform,
+    ) {
+        if hi <= node_lo || node_hi <= lo {
+            return;
+        }
+        if lo <= node_lo && node_hi <= hi {
+            self.pending[node] = self.pending[node].then(transform);
+            return;
+        }
+        self.push_down(node);
+        let mid = (node_lo + node_hi) / 2;
+        self.range_apply(node * 2, node_lo, mid, lo, hi, transform);
+        self.range_apply(node * 2 + 1, mid, node_hi, lo, hi, transform);
+    }
+
+    fn point_query(
+        &self,
+        node: usize,
+        node_lo: usize,
+        node_hi: usize,
+        index: usize,
+    ) -> TimeCompatible {
+        let transform = self.pending[node];
+        if node_hi - node_lo == 1 {
+            transform.apply(self.values[index])
+        } else {
+            let mid = (node_lo + node_hi) / 2;
+            let value = if index < mid {
+                self.point_query(node * 2, node_lo, mid, index)
+            } else {
+                self.point_query(node * 2 + 1, mid, node_hi, index)
+            };
+            transform.apply(value)
+        }
+    }
+
+    fn apply(&mut self, lo: usize, hi: usize, transform: AffineTransform) {
+        if lo < hi {
+            self.range_apply(1, 0, self.len, lo, hi, transform);
+        }
+    }
+
+    fn at(&self, index: usize) -> TimeCompatible {
+        self.point_query(1, 0, self.len, index)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Automation {
+    times: Vec<Time>,
+    tree: SegmentTree,
+}
+
+impl Automation {
+    pub fn try_new<I>(breakpoints: I) -> Result<Self, BadAutomationPoint>
+    where
+        I: IntoIterator<Item = (Time, TimeCompatible)>,
+    {
+        let mut points: Vec<(Time, TimeCompatible)> =
+            breakpoints.into_iter().collect();
+
+        if points.iter().any(|(seconds, _)| seconds.is_nan()) {
+            return Err(BadAutomationPoint::Nan);
+        }
+        if points.is_empty() {
+            return Err(BadAutomationPoint::Empty);
+        }
+
+        points.sort_by(|(t1, _), (t2, _)| {
+            t1.partial_cmp(t2).expect("NaN breakpoints already rejected")
+        });
+        for window in points.windows(2) {
+            if window[0].0 == window[1].0 {
+                return Err(BadAutomationPoint::Duplicated(window[0].0));
+            }
+        }
+
+        let (times, values) = points.into_iter().unzip();
+        Ok(Self { times, tree: SegmentTree::new(values) })
+    }
+
+    pub fn new<I>(breakpoints: I) -> Self
+    where
+        I: IntoIterator<Item = (Time, TimeCompatible)>,
+    {
+        Self::try_new(breakpoints).expect("bad automation breakpoints")
+    }
+
+    fn search(&self, seconds: Time) -> Result<usize, usize> {
+        self.times.binary_search_by(|step_at| {
+            step_at.partial_cmp(&seconds).unwrap_or(Ordering::Greater)
+        })
+    }
+
+    pub fn range_scale(&mut self, start: Time, end: Time, scale: TimeCompatible) {
+        self.apply_range(start, end, AffineTransform { scale, offset: 0.0 });
+    }
+
+    pub fn range_offset(&mut self, start: Time, end: Time, offset: TimeCompatible) {
+        self.apply_range(start, end, AffineTransform { scale: 1.0, offset });
+    }
+
+    fn apply_range(&mut self, start: Time, end: Time, transform: AffineTransform) {
+        let lo = self.times.partition_point(|&step_at| step_at < start);
+        let hi = self.times.partition_point(|&step_at| step_at <= end);
+        self.tree.apply(lo, hi, transform);
+    }
+}
+
+impl TimeFn for Automation {
+    type Output = TimeCompatible;
+
+    fn at(&self, seconds: Time) -> Self::Output {
+        match self.search(seconds) {
+            Ok(i) => self.tree.at(i),
+            Err(0) => self.tree.at(0),
+            Err(i) if i >= self.times.len() => self.tree.at(self.times.len() - 1),
+            Err(i) => {
+                let (t0, t1) = (self.times[i - 1], self.times[i]);
+                let (v0, v1) = (self.tree.at(i - 1), self.tree.at(i));
+                v0 + (v1 - v0) * (seconds - t0) / (t1 - t0)
+            },
+        }
+    }
+}
+
 pub fn time_fn<F, A>(closure: F) -> TimeClosureFn<F>
 where
     F: Fn(Time) -> A,
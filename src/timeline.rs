@@ -0,0 +1,182 @@
+use core::fmt;
+use std::sync::Arc;
+
+use thiserror::Error;
+
+use crate::{
+    player::{Backend, Sample, SampleSource},
+    time::{DynTimeFn, Time, TimeFn},
+};
+
+#[derive(Debug, Clone, Error)]
+#[error("timeline requires at least one track")]
+pub struct NoTracks;
+
+#[derive(Clone)]
+pub struct Clip {
+    time_fn: Arc<DynTimeFn<'static, Sample>>,
+    offset: Time,
+    start: Time,
+    end: Time,
+}
+
+impl fmt::Debug for Clip {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_struct("Clip")
+            .field("offset", &self.offset)
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .finish()
+    }
+}
+
+impl Clip {
+    pub fn new(
+        time_fn: Arc<DynTimeFn<'static, Sample>>,
+        offset: Time,
+        start: Time,
+        end: Time,
+    ) -> Self {
+        Self { time_fn, offset, start, end }
+    }
+
+    fn at(&self, global_time: Time) -> Sample {
+        let local_time = global_time - self.offset;
+        if local_time >= self.start && local_time <= self.end {
+            self.time_fn.at(local_time)
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Track {
+    clips: Vec<Clip>,
+}
+
+impl Track {
+    pub fn new<I>(clips: I) -> Self
+    where
+        I: IntoIterator<Item = Clip>,
+    {
+        Self { clips: clips.into_iter().collect() }
+    }
+}
+
+impl TimeFn for Track {
+    type Output = Sample;
+
+    fn at(&self, seconds: Time) -> Self::Output {
+        self.clips.iter().map(|clip| clip.at(seconds)).sum()
+    }
+}
+
+fn wrap_loop(loop_region: Option<(Time, Time)>) -> impl Fn(Time) -> Time {
+    move |seconds| match loop_region {
+        Some((loop_start, loop_end)) if loop_end > loop_start => {
+            if seconds < loop_start {
+                seconds
+            } else {
+                loop_start + (seconds - loop_start) % (loop_end - loop_start)
+            }
+        },
+        _ => seconds,
+    }
+}
+
+#[derive(Debug)]
+pub struct Timeline<B> {
+    tracks: Arc<[Track]>,
+    backend: B,
+    sample_rate: u32,
+    position: Time,
+    loop_region: Option<(Time, Time)>,
+}
+
+impl<B> Clone for Timeline<B>
+where
+    B: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            tracks: self.tracks.clone(),
+            backend: self.backend.clone(),
+            sample_rate: self.sample_rate,
+            position: self.position,
+            loop_region: self.loop_region,
+        }
+    }
+}
+
+impl<B> Timeline<B>
+where
+    B: Backend,
+{
+    pub fn new<I>(tracks: I, backend: B) -> Result<Self, NoTracks>
+    where
+        I: IntoIterator<Item = Track>,
+    {
+        let tracks: Arc<[Track]> = tracks.into_iter().collect();
+        if tracks.is_empty() {
+            Err(NoTracks)
+        } else {
+            Ok(Self {
+                tracks,
+                backend,
+                sample_rate: 48_000,
+                position: 0.0,
+                loop_region: None,
+            })
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn set_sample_rate(&mut self, value: u32) {
+        self.sample_rate = value;
+    }
+
+    pub fn seek(&mut self, time: Time) {
+        self.position = time;
+    }
+
+    pub fn set_loop(&mut self, region: Option<(Time, Time)>) {
+        self.loop_region = region;
+    }
+
+    pub fn play(&mut self) {
+        let loop_region = self.loop_region;
+        let end = match loop_region {
+            Some((loop_start, loop_end)) if loop_end > loop_start => Time::INFINITY,
+            _ => self
+                .tracks
+                .iter()
+                .flat_map(|track| track.clips.iter())
+                .map(|clip| clip.offset + clip.end)
+                .fold(self.position, Time::max),
+        };
+
+        let channels: Arc<[_]> = self
+            .tracks
+            .iter()
+            .cloned()
+            .map(|track| track.proxy(wrap_loop(loop_region)))
+            .collect();
+
+        let source = SampleSource::new(channels, self.sample_rate, self.position, end);
+
+        self.backend.stop();
+        self.backend.play(source);
+    }
+
+    pub fn stop(&mut self) {
+        self.backend.stop();
+    }
+
+    pub fn wait(&mut self) {
+        self.backend.wait();
+    }
+}